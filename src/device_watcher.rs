@@ -0,0 +1,308 @@
+//! Hotplug-aware monitoring of `/dev/input`.
+//!
+//! [`enumerate`](crate::enumerate) gives a one-shot snapshot of the devices present in
+//! `/dev/input` at a single instant. Long-running programs such as remappers, compositors and
+//! input daemons instead need to learn about devices as they are plugged in or removed, without
+//! resorting to polling the directory themselves.
+//!
+//! [`DeviceWatcher`] wraps an `inotify` watch on `/dev/input` and turns directory changes into a
+//! stream of [`DeviceEvent`]s. Like [`Device`], it implements [`AsRawFd`] so it can be driven from
+//! `epoll` or an async runtime: the fd becomes readable whenever there are events to drain.
+
+use crate::Device;
+use std::collections::{HashSet, VecDeque};
+use std::ffi::OsStr;
+use std::io;
+use std::mem;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+
+const INPUT_DIR: &str = "/dev/input";
+
+/// A change observed on `/dev/input` by a [`DeviceWatcher`].
+#[derive(Debug)]
+pub enum DeviceEvent {
+    /// A new `eventX` node appeared and was successfully opened.
+    Added(PathBuf, Device),
+    /// An `eventX` node was removed. The device behind it, if any was still held open by the
+    /// caller, is now defunct.
+    Removed(PathBuf),
+}
+
+/// Watches `/dev/input` and yields [`DeviceEvent`]s as devices come and go.
+///
+/// The watcher reacts to `IN_CREATE`, `IN_DELETE` and `IN_ATTRIB` on names matching `event[0-9]+`.
+/// `IN_ATTRIB` matters because a freshly created `eventX` node frequently appears before udev has
+/// finished fixing up its permissions, so opening it immediately can momentarily fail with
+/// `EACCES`. Rather than dropping such a device, the watcher keeps it in a small pending set and
+/// re-attempts the open on each subsequent `IN_ATTRIB` for that node.
+///
+/// The first call to [`next`](DeviceWatcher::next) or [`drain`](DeviceWatcher::drain) also surfaces
+/// a full enumeration of the devices already present, so a caller gets the current devices and all
+/// future changes through a single interface.
+pub struct DeviceWatcher {
+    fd: RawFd,
+    wd: libc::c_int,
+    /// Parsed-but-not-yet-returned events.
+    ready: VecDeque<DeviceEvent>,
+    /// Nodes that appeared but could not be opened yet (typically `EACCES`); retried on `ATTRIB`.
+    pending: HashSet<PathBuf>,
+    /// Nodes already surfaced to the caller as `Added`, so the same node is not reported twice when
+    /// a buffered `IN_CREATE` races the initial enumeration. Entries are dropped on `Removed`.
+    surfaced: HashSet<PathBuf>,
+}
+
+impl DeviceWatcher {
+    /// Creates a watcher on `/dev/input`.
+    ///
+    /// The underlying `inotify` fd is opened nonblocking and close-on-exec; [`next`] uses `poll` to
+    /// block when no events are queued. The watch is armed *before* the initial enumeration runs,
+    /// so a device plugged in during construction surfaces as a buffered `IN_CREATE` rather than
+    /// being missed; the `surfaced` set then suppresses the duplicate the enumeration would emit.
+    ///
+    /// The current devices are queued as `Added` events up front, so a caller driving the watcher
+    /// purely through `epoll`/`drain` still sees them without waiting for the next hotplug.
+    ///
+    /// [`next`]: DeviceWatcher::next
+    pub fn new() -> io::Result<Self> {
+        // SAFETY: inotify_init1 takes a flag bitmask and returns a fd or -1.
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let path = std::ffi::CString::new(INPUT_DIR).unwrap();
+        let mask = libc::IN_CREATE | libc::IN_DELETE | libc::IN_ATTRIB;
+        // SAFETY: fd is a valid inotify fd and path is a valid C string.
+        let wd = unsafe { libc::inotify_add_watch(fd, path.as_ptr(), mask) };
+        if wd < 0 {
+            let err = io::Error::last_os_error();
+            // SAFETY: fd was successfully opened above.
+            unsafe { libc::close(fd) };
+            return Err(err);
+        }
+
+        let mut watcher = DeviceWatcher {
+            fd,
+            wd,
+            ready: VecDeque::new(),
+            pending: HashSet::new(),
+            surfaced: HashSet::new(),
+        };
+
+        // Surface the devices already present eagerly, now that the watch is armed. Any node that
+        // appears from here on arrives as an inotify event and is de-duplicated against `surfaced`.
+        for (path, device) in crate::enumerate() {
+            watcher.surfaced.insert(path.clone());
+            watcher.ready.push_back(DeviceEvent::Added(path, device));
+        }
+
+        Ok(watcher)
+    }
+
+    /// Blocks until at least one [`DeviceEvent`] is available and returns it.
+    ///
+    /// The devices already present at construction are returned first, followed by hotplug changes
+    /// as they occur.
+    pub fn next(&mut self) -> io::Result<DeviceEvent> {
+        loop {
+            if let Some(ev) = self.ready.pop_front() {
+                return Ok(ev);
+            }
+            self.block()?;
+            self.fill()?;
+        }
+    }
+
+    /// Returns every [`DeviceEvent`] that can be produced without blocking.
+    ///
+    /// This is the nonblocking counterpart to [`next`](DeviceWatcher::next). The devices present at
+    /// construction are already queued, so the first `drain()` returns them even before any hotplug
+    /// has made the fd readable; subsequent calls return whatever the fd has since buffered. Returns
+    /// an empty vector if nothing is ready.
+    pub fn drain(&mut self) -> io::Result<Vec<DeviceEvent>> {
+        self.fill()?;
+        Ok(self.ready.drain(..).collect())
+    }
+
+    /// Blocks on the inotify fd until it becomes readable.
+    fn block(&self) -> io::Result<()> {
+        let mut pfd = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        loop {
+            // SAFETY: pfd points to a single valid pollfd for the duration of the call.
+            let res = unsafe { libc::poll(&mut pfd, 1, -1) };
+            if res < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(err);
+            }
+            return Ok(());
+        }
+    }
+
+    /// Reads all currently available inotify events and turns them into [`DeviceEvent`]s.
+    fn fill(&mut self) -> io::Result<()> {
+        // inotify requires a buffer large enough for at least one event including its name. The
+        // buffer is over-aligned so the first `inotify_event` header lands on a properly aligned
+        // address; the kernel pads each record so the ones that follow stay aligned too.
+        let mut buf = AlignedBuf([0u8; 4096]);
+        loop {
+            // SAFETY: buf.0 is a valid, writable byte buffer of the stated length.
+            let n = unsafe {
+                libc::read(
+                    self.fd,
+                    buf.0.as_mut_ptr() as *mut libc::c_void,
+                    buf.0.len(),
+                )
+            };
+            if n < 0 {
+                let err = io::Error::last_os_error();
+                match err.kind() {
+                    io::ErrorKind::WouldBlock => return Ok(()),
+                    io::ErrorKind::Interrupted => continue,
+                    _ => return Err(err),
+                }
+            }
+            if n == 0 {
+                return Ok(());
+            }
+            self.parse(&buf.0[..n as usize]);
+        }
+    }
+
+    /// Parses a batch of raw `inotify_event` records out of `bytes`.
+    fn parse(&mut self, bytes: &[u8]) {
+        let header_len = mem::size_of::<libc::inotify_event>();
+        let mut offset = 0;
+        while offset + header_len <= bytes.len() {
+            // SAFETY: the slice holds at least a full header at `offset`; inotify_event is a
+            // plain-old-data struct with no invalid bit patterns for the fields we read.
+            let event = unsafe { &*(bytes.as_ptr().add(offset) as *const libc::inotify_event) };
+            let name_len = event.len as usize;
+            let name_start = offset + header_len;
+            let name_end = name_start + name_len;
+            if name_end > bytes.len() {
+                break;
+            }
+
+            let raw_name = &bytes[name_start..name_end];
+            // The name is NUL-padded to the record boundary; trim at the first NUL.
+            let name = raw_name
+                .iter()
+                .position(|&b| b == 0)
+                .map_or(raw_name, |nul| &raw_name[..nul]);
+            if !name.is_empty() {
+                self.handle(OsStr::from_bytes(name), event.mask);
+            }
+
+            offset = name_end;
+        }
+    }
+
+    /// Applies a single named inotify record.
+    fn handle(&mut self, name: &OsStr, mask: u32) {
+        if !is_event_node(name) {
+            return;
+        }
+        let path = Path::new(INPUT_DIR).join(name);
+
+        if mask & libc::IN_DELETE != 0 {
+            self.pending.remove(&path);
+            self.surfaced.remove(&path);
+            self.ready.push_back(DeviceEvent::Removed(path));
+            return;
+        }
+
+        // A node already surfaced by the initial enumeration must not be reported again when its
+        // buffered `IN_CREATE` is drained.
+        if self.surfaced.contains(&path) {
+            return;
+        }
+
+        // A node may appear via CREATE before its permissions are set, so we retry on the ATTRIB
+        // events that follow the udev fixup. A bare ATTRIB on a node we are not waiting for (e.g.
+        // one we already opened) carries no new information, so only CREATE, or an ATTRIB for a
+        // node actually in the pending set, triggers an open attempt.
+        let is_create = mask & libc::IN_CREATE != 0;
+        let retry_pending = mask & libc::IN_ATTRIB != 0 && self.pending.contains(&path);
+        if is_create || retry_pending {
+            match Device::open(&path) {
+                Ok(device) => {
+                    self.pending.remove(&path);
+                    self.surfaced.insert(path.clone());
+                    self.ready.push_back(DeviceEvent::Added(path, device));
+                }
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                    // Not ready yet; keep it pending and wait for the next ATTRIB.
+                    self.pending.insert(path);
+                }
+                Err(_) => {
+                    // Vanished again or otherwise unusable; forget it.
+                    self.pending.remove(&path);
+                }
+            }
+        }
+    }
+}
+
+impl AsRawFd for DeviceWatcher {
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd
+    }
+}
+
+impl Drop for DeviceWatcher {
+    fn drop(&mut self) {
+        // SAFETY: fd is a valid inotify fd owned by this watcher; wd belongs to it.
+        unsafe {
+            libc::inotify_rm_watch(self.fd, self.wd);
+            libc::close(self.fd);
+        }
+    }
+}
+
+/// A read buffer over-aligned for `inotify_event`, which has an alignment of 4.
+///
+/// Reading into a plain `[u8; N]` (alignment 1) and then forming a `&inotify_event` to its start
+/// would be undefined behaviour even when the load happens to work, so the bytes are wrapped in an
+/// aligned newtype instead.
+#[repr(align(8))]
+struct AlignedBuf([u8; 4096]);
+
+/// Returns `true` for names matching `event[0-9]+`.
+fn is_event_node(name: &OsStr) -> bool {
+    let bytes = name.as_bytes();
+    match bytes.strip_prefix(b"event") {
+        Some(rest) => !rest.is_empty() && rest.iter().all(|b| b.is_ascii_digit()),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_event_node;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn matches_event_nodes() {
+        assert!(is_event_node(OsStr::new("event0")));
+        assert!(is_event_node(OsStr::new("event12")));
+    }
+
+    #[test]
+    fn rejects_non_event_nodes() {
+        // No trailing number, a non-digit suffix, unrelated nodes, and the bare prefix.
+        assert!(!is_event_node(OsStr::new("event")));
+        assert!(!is_event_node(OsStr::new("eventX")));
+        assert!(!is_event_node(OsStr::new("event1a")));
+        assert!(!is_event_node(OsStr::new("mouse0")));
+        assert!(!is_event_node(OsStr::new("by-path")));
+    }
+}
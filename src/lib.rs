@@ -102,13 +102,17 @@ mod attribute_set;
 mod compat;
 mod constants;
 mod device_state;
+mod device_watcher;
 mod error;
 mod ff;
 mod inputid;
 pub mod raw_stream;
+mod record;
 mod scancodes;
 mod sync_stream;
 mod sys;
+#[cfg(feature = "serde")]
+mod serde_support;
 pub mod uinput;
 mod event_variants;
 
@@ -121,10 +125,12 @@ pub use event_variants::*;
 pub use attribute_set::{AttributeSet, AttributeSetRef, EvdevEnum};
 pub use constants::*;
 pub use device_state::DeviceState;
+pub use device_watcher::{DeviceEvent, DeviceWatcher};
 pub use error::Error;
 pub use ff::*;
 pub use inputid::*;
 pub use raw_stream::{AutoRepeat, FFEffect};
+pub use record::{Player, Recorder};
 pub use scancodes::*;
 pub use sync_stream::*;
 
@@ -0,0 +1,230 @@
+//! Recording an event stream and replaying it through `uinput`.
+//!
+//! A [`Recorder`] consumes any iterator of [`InputEvent`]s — for example the one returned by
+//! [`Device::fetch_events`](crate::Device::fetch_events) — and serializes each event as a
+//! `(timestamp, type, code, value)` tuple to a [`Write`]r, preserving the `EV_SYN`/`SYN_REPORT`
+//! frame boundaries and the original timestamps. A [`Player`] reads that stream back and emits it
+//! to a [`VirtualDevice`](crate::uinput::VirtualDevice), sleeping between frames to reproduce the
+//! original timing.
+//!
+//! Recorded `SystemTime`s are absolute, so replay never sleeps to wall-clock times. Instead the
+//! [`Player`] computes the delta between consecutive `SYN_REPORT` frames and sleeps for that gap
+//! (optionally scaled by a speed multiplier, or skipped entirely). Emitted events are rebuilt with
+//! [`InputEvent::new`], leaving the time field zeroed so the kernel stamps them as it delivers
+//! them.
+
+use crate::uinput::VirtualDevice;
+use crate::{EvdevEvent, EventType, InputEvent, SynchronizationType};
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant, SystemTime};
+
+/// On-disk size of a single serialized event record: 8-byte secs, 4-byte nanos, type, code, value.
+const RECORD_LEN: usize = 8 + 4 + 2 + 2 + 4;
+
+/// Records an [`InputEvent`] stream to a [`Write`]r as `(timestamp, type, code, value)` tuples.
+///
+/// The format is a flat sequence of fixed-width little-endian records, so it can be streamed to a
+/// file or a socket without buffering the whole capture in memory.
+pub struct Recorder<W> {
+    writer: W,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Creates a recorder writing to `writer`.
+    pub fn new(writer: W) -> Self {
+        Recorder { writer }
+    }
+
+    /// Writes a single event to the underlying writer.
+    pub fn record(&mut self, event: &InputEvent) -> io::Result<()> {
+        let (secs, nanos) = split_timestamp(event.timestamp());
+        let mut buf = [0u8; RECORD_LEN];
+        buf[0..8].copy_from_slice(&secs.to_le_bytes());
+        buf[8..12].copy_from_slice(&nanos.to_le_bytes());
+        buf[12..14].copy_from_slice(&event.event_type().to_le_bytes());
+        buf[14..16].copy_from_slice(&event.code().to_le_bytes());
+        buf[16..20].copy_from_slice(&event.value().to_le_bytes());
+        self.writer.write_all(&buf)
+    }
+
+    /// Drains `events` into the writer, recording every event including the `SYN_REPORT`
+    /// terminators that mark frame boundaries.
+    pub fn record_all<I>(&mut self, events: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = InputEvent>,
+    {
+        for event in events {
+            self.record(&event)?;
+        }
+        Ok(())
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Replays a recorded stream to a [`VirtualDevice`](crate::uinput::VirtualDevice).
+///
+/// By default the player reproduces the original inter-frame gaps. Use [`playback_speed`] to scale
+/// those gaps (values above `1.0` play faster, below `1.0` slower) or [`as_fast_as_possible`] to
+/// drop the sleeps entirely, which is what deterministic input tests usually want.
+///
+/// [`playback_speed`]: Player::playback_speed
+/// [`as_fast_as_possible`]: Player::as_fast_as_possible
+pub struct Player<R> {
+    reader: R,
+    speed: f64,
+    realtime: bool,
+}
+
+impl<R: Read> Player<R> {
+    /// Creates a player reading from `reader`, reproducing the original timing.
+    pub fn new(reader: R) -> Self {
+        Player {
+            reader,
+            speed: 1.0,
+            realtime: true,
+        }
+    }
+
+    /// Scales the inter-frame gaps by `speed`. A `speed` of `2.0` replays twice as fast.
+    ///
+    /// Non-positive values are clamped to a tiny positive number to avoid dividing by zero.
+    pub fn playback_speed(mut self, speed: f64) -> Self {
+        self.speed = if speed > 0.0 { speed } else { f64::MIN_POSITIVE };
+        self.realtime = true;
+        self
+    }
+
+    /// Emits every frame back-to-back without sleeping, ignoring the recorded timing.
+    pub fn as_fast_as_possible(mut self) -> Self {
+        self.realtime = false;
+        self
+    }
+
+    /// Replays the whole stream to `device`, blocking until it is exhausted.
+    ///
+    /// Sleeps are computed from the delta between consecutive `SYN_REPORT` timestamps rather than
+    /// absolute wall-clock times, so a capture taken yesterday still replays with today's timing.
+    pub fn play(mut self, device: &mut VirtualDevice) -> io::Result<()> {
+        let mut frame: Vec<InputEvent> = Vec::new();
+        // Timestamp of the previous frame's SYN_REPORT and when we finished emitting it, used to
+        // schedule the next frame relative to the last rather than to absolute wall-clock time.
+        let mut last: Option<(SystemTime, Instant)> = None;
+
+        while let Some((time, event)) = self.read_record()? {
+            let is_report = event.0 == EventType::SYNCHRONIZATION.0
+                && event.1 == SynchronizationType::SYN_REPORT.0;
+            if !is_report {
+                frame.push(InputEvent::new(event.0, event.1, event.2));
+                continue;
+            }
+
+            // The recorded SYN_REPORT only marks the frame boundary and carries the timing; it is
+            // not forwarded, because `VirtualDevice::emit` appends its own SYN_REPORT after the
+            // frame. Pushing it here would emit two consecutive reports per frame.
+            if self.realtime {
+                if let Some((prev_time, prev_instant)) = last {
+                    if let Ok(gap) = time.duration_since(prev_time) {
+                        let scaled = gap.div_f64(self.speed);
+                        // Subtract the time passed since the previous frame was emitted, so reading
+                        // this frame's records does not lengthen the gap.
+                        if let Some(remaining) = scaled.checked_sub(prev_instant.elapsed()) {
+                            std::thread::sleep(remaining);
+                        }
+                    }
+                }
+            }
+
+            device.emit(&frame)?;
+            frame.clear();
+            last = Some((time, Instant::now()));
+        }
+
+        // Flush any trailing events that were not terminated by a SYN_REPORT.
+        if !frame.is_empty() {
+            device.emit(&frame)?;
+        }
+        Ok(())
+    }
+
+    /// Reads one serialized record, returning its recorded timestamp and raw `(type, code, value)`.
+    fn read_record(&mut self) -> io::Result<Option<(SystemTime, (u16, u16, i32))>> {
+        let mut buf = [0u8; RECORD_LEN];
+        match self.reader.read_exact(&mut buf) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let secs = u64::from_le_bytes(buf[0..8].try_into().unwrap());
+        let nanos = u32::from_le_bytes(buf[8..12].try_into().unwrap());
+        let type_ = u16::from_le_bytes(buf[12..14].try_into().unwrap());
+        let code = u16::from_le_bytes(buf[14..16].try_into().unwrap());
+        let value = i32::from_le_bytes(buf[16..20].try_into().unwrap());
+        let time = SystemTime::UNIX_EPOCH + Duration::new(secs, nanos);
+        Ok(Some((time, (type_, code, value))))
+    }
+}
+
+/// Splits a `SystemTime` into seconds and nanoseconds since the Unix epoch, clamping times before
+/// the epoch to zero (input events are never stamped before 1970 in practice).
+fn split_timestamp(time: SystemTime) -> (u64, u32) {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(dur) => (dur.as_secs(), dur.subsec_nanos()),
+        Err(_) => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_timestamp, Player, Recorder, RECORD_LEN};
+    use crate::{EventType, InputEvent, SynchronizationType};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn split_timestamp_handles_epoch_and_before() {
+        let t = SystemTime::UNIX_EPOCH + Duration::new(7, 500_000_000);
+        assert_eq!(split_timestamp(t), (7, 500_000_000));
+        // Times before the epoch clamp to zero rather than panicking.
+        let before = SystemTime::UNIX_EPOCH - Duration::from_secs(1);
+        assert_eq!(split_timestamp(before), (0, 0));
+    }
+
+    #[test]
+    fn records_round_trip_through_read_record() {
+        // A two-event frame: a key press followed by its SYN_REPORT terminator.
+        let events = [
+            InputEvent::new(EventType::KEY.0, 30, 1),
+            InputEvent::new(EventType::SYNCHRONIZATION.0, SynchronizationType::SYN_REPORT.0, 0),
+        ];
+
+        let mut recorder = Recorder::new(Vec::new());
+        recorder.record_all(events).unwrap();
+        let bytes = recorder.into_inner().unwrap();
+        assert_eq!(bytes.len(), 2 * RECORD_LEN);
+
+        let mut player = Player::new(bytes.as_slice());
+        let (time, first) = player.read_record().unwrap().unwrap();
+        // InputEvent::new zeroes the time field, so the recorded timestamp is the epoch.
+        assert_eq!(time, SystemTime::UNIX_EPOCH);
+        assert_eq!(first, (EventType::KEY.0, 30, 1));
+
+        let (_, second) = player.read_record().unwrap().unwrap();
+        assert_eq!(
+            second,
+            (EventType::SYNCHRONIZATION.0, SynchronizationType::SYN_REPORT.0, 0)
+        );
+
+        // Exhausted stream reports end-of-input rather than erroring.
+        assert!(player.read_record().unwrap().is_none());
+    }
+
+    #[test]
+    fn truncated_record_reports_eof() {
+        let mut player = Player::new(&[0u8; RECORD_LEN - 1][..]);
+        assert!(player.read_record().unwrap().is_none());
+    }
+}
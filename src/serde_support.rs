@@ -0,0 +1,303 @@
+//! `serde` support for [`InputEvent`] and the event-code newtypes.
+//!
+//! Enabled by the `serde` feature. Events and axes are serialized by their symbolic name
+//! (`KEY_ENTER`, `REL_X`) rather than their raw `u16`, so configuration files written against this
+//! crate stay human-readable and remain stable across kernel header changes that renumber codes.
+//!
+//! [`InputEvent`] is represented as an adjacently-tagged structure mirroring
+//! [`InputEvent::kind`](crate::InputEvent::kind): the tag is the event-type category, the payload
+//! carries the symbolic `code` and the raw `i32` `value`. Each `code` delegates to the
+//! corresponding newtype's own name-based `Serialize`/`Deserialize`, which reuses the
+//! [`EvdevEnum`](crate::EvdevEnum) machinery. A code the current headers do not name round-trips
+//! by number within its own category; events of a type this crate does not model round-trip
+//! through the [`Other`](crate::InputEvent::Other) variant unchanged.
+//!
+//! Where several constants share one numeric value (e.g. `BTN_SOUTH` and `BTN_A`, both `0x130`),
+//! the code serializes as the single canonical name the newtype's [`Debug`] impl renders, and only
+//! that canonical name is accepted on deserialize; the raw decimal number, written as a string
+//! (`"304"`), is also accepted as a fallback.
+//!
+//! On deserialize the event is rebuilt through the existing `From<input_event>` path via
+//! [`InputEvent::new`](crate::InputEvent::new), leaving the time field zeroed — timestamps are not
+//! part of the config-facing representation.
+
+use crate::{
+    AbsAxisType, EventType, FFStatusType, FFType, InputEvent, KeyType, LedType, MiscType,
+    PowerType, RelAxisType, RepeatType, SoundType, SwitchType, SynchronizationType, UInputType,
+};
+use crate::{EvdevEvent, EvdevEnum};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write as _};
+
+/// Serializes an event-code newtype by its symbolic name (`KEY_ENTER`, `REL_X`).
+///
+/// The name comes from the newtype's own [`Debug`] impl, which the `evdev_enum!` machinery already
+/// renders as the symbolic constant, so the wire form stays stable across kernel header renumbering.
+fn serialize_symbolic<T, S>(code: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: EvdevEnum + fmt::Debug,
+    S: serde::Serializer,
+{
+    let mut name = String::new();
+    // Infallible: writing to a String never errors.
+    let _ = write!(name, "{:?}", code);
+    serializer.serialize_str(&name)
+}
+
+/// Deserializes an event-code newtype from its symbolic name.
+///
+/// The reverse lookup reuses the [`EvdevEnum`] index space: every code is reconstructed with
+/// [`EvdevEnum::from_index`] and compared against its [`Debug`] name. Only the canonical name (the
+/// one [`Debug`] renders) matches by name; a non-canonical alias of the same value — such as
+/// `BTN_A` for `BTN_SOUTH` — is not recognised and must be written either as the canonical name or
+/// as the raw decimal number in a string. Codes the current headers do not name likewise serialize
+/// as a string holding the number and round-trip through that numeric form, mirroring how an
+/// unknown event type falls through to
+/// [`Other`](crate::InputEvent::Other).
+fn deserialize_symbolic<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: EvdevEnum + fmt::Debug,
+    D: serde::Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    let mut buf = String::new();
+    for index in 0..=u16::MAX as usize {
+        let code = T::from_index(index);
+        buf.clear();
+        let _ = write!(buf, "{:?}", code);
+        if buf == name {
+            return Ok(code);
+        }
+    }
+    name.parse::<u16>()
+        .map(|raw| T::from_index(raw as usize))
+        .map_err(|_| {
+            serde::de::Error::custom(format!(
+                "unknown evdev code name: {name} (expected a canonical name or a raw number)"
+            ))
+        })
+}
+
+/// Implements name-based `Serialize`/`Deserialize` for each event-code newtype.
+macro_rules! impl_symbolic_serde {
+    ($($t:ty),+ $(,)?) => {$(
+        impl Serialize for $t {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_symbolic(self, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserialize_symbolic(deserializer)
+            }
+        }
+    )+};
+}
+
+impl_symbolic_serde!(
+    SynchronizationType,
+    KeyType,
+    RelAxisType,
+    AbsAxisType,
+    MiscType,
+    SwitchType,
+    LedType,
+    SoundType,
+    RepeatType,
+    FFType,
+    PowerType,
+    FFStatusType,
+    UInputType,
+);
+
+/// Wire form of an [`InputEvent`]: a category tag plus the symbolic code and raw value.
+///
+/// The variant names match the [`InputEventKind`](crate::InputEventKind) categories and the `code`
+/// field of each variant uses the matching newtype so it serializes by symbolic name.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "event")]
+enum InputEventRepr {
+    Synchronization { code: SynchronizationType, value: i32 },
+    Key { code: KeyType, value: i32 },
+    RelAxis { code: RelAxisType, value: i32 },
+    AbsAxis { code: AbsAxisType, value: i32 },
+    Misc { code: MiscType, value: i32 },
+    Switch { code: SwitchType, value: i32 },
+    Led { code: LedType, value: i32 },
+    Sound { code: SoundType, value: i32 },
+    Repeat { code: RepeatType, value: i32 },
+    ForceFeedback { code: FFType, value: i32 },
+    Power { code: PowerType, value: i32 },
+    ForceFeedbackStatus { code: FFStatusType, value: i32 },
+    UInput { code: UInputType, value: i32 },
+    /// Codes from event types this crate does not model keep their raw numbers so they round-trip.
+    Other { event_type: u16, code: u16, value: i32 },
+}
+
+impl From<&InputEvent> for InputEventRepr {
+    fn from(event: &InputEvent) -> Self {
+        match *event {
+            InputEvent::Synchronization(ev) => InputEventRepr::Synchronization {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::Key(ev) => InputEventRepr::Key {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::RelAxis(ev) => InputEventRepr::RelAxis {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::AbsAxis(ev) => InputEventRepr::AbsAxis {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::Misc(ev) => InputEventRepr::Misc {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::Switch(ev) => InputEventRepr::Switch {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::Led(ev) => InputEventRepr::Led {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::Sound(ev) => InputEventRepr::Sound {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::Repeat(ev) => InputEventRepr::Repeat {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::ForceFeedback(ev) => InputEventRepr::ForceFeedback {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::Power(ev) => InputEventRepr::Power {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::ForceFeedbackStatus(ev) => InputEventRepr::ForceFeedbackStatus {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::UInput(ev) => InputEventRepr::UInput {
+                code: ev.kind(),
+                value: ev.value(),
+            },
+            InputEvent::Other(ev) => InputEventRepr::Other {
+                event_type: ev.event_type(),
+                code: ev.code(),
+                value: ev.value(),
+            },
+        }
+    }
+}
+
+impl From<InputEventRepr> for InputEvent {
+    fn from(repr: InputEventRepr) -> Self {
+        // Rebuild the raw (type, code, value) triple and go through the From<input_event> path, so
+        // categorisation matches events read straight from the kernel.
+        let (type_, code, value) = match repr {
+            InputEventRepr::Synchronization { code, value } => {
+                (EventType::SYNCHRONIZATION.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::Key { code, value } => (EventType::KEY.0, code.to_index() as u16, value),
+            InputEventRepr::RelAxis { code, value } => {
+                (EventType::RELATIVE.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::AbsAxis { code, value } => {
+                (EventType::ABSOLUTE.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::Misc { code, value } => {
+                (EventType::MISC.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::Switch { code, value } => {
+                (EventType::SWITCH.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::Led { code, value } => (EventType::LED.0, code.to_index() as u16, value),
+            InputEventRepr::Sound { code, value } => {
+                (EventType::SOUND.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::Repeat { code, value } => {
+                (EventType::REPEAT.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::ForceFeedback { code, value } => {
+                (EventType::FORCEFEEDBACK.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::Power { code, value } => {
+                (EventType::POWER.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::ForceFeedbackStatus { code, value } => {
+                (EventType::FORCEFEEDBACKSTATUS.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::UInput { code, value } => {
+                (EventType::UINPUT.0, code.to_index() as u16, value)
+            }
+            InputEventRepr::Other {
+                event_type,
+                code,
+                value,
+            } => (event_type, code, value),
+        };
+        InputEvent::new(type_, code, value)
+    }
+}
+
+impl Serialize for InputEvent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        InputEventRepr::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for InputEvent {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        InputEventRepr::deserialize(deserializer).map(InputEvent::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{EvdevEvent, EventType, InputEvent, KeyType, RelAxisType};
+
+    fn round_trip(event: InputEvent) -> InputEvent {
+        let json = serde_json::to_string(&event).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn assert_same(a: InputEvent, b: InputEvent) {
+        assert_eq!(a.event_type(), b.event_type());
+        assert_eq!(a.code(), b.code());
+        assert_eq!(a.value(), b.value());
+    }
+
+    #[test]
+    fn key_event_serializes_by_symbolic_name() {
+        let event = InputEvent::new(EventType::KEY.0, KeyType::KEY_ENTER.0, 1);
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("KEY_ENTER"), "expected symbolic name in {json}");
+        assert!(!json.contains(&KeyType::KEY_ENTER.0.to_string()));
+        assert_same(event, round_trip(event));
+    }
+
+    #[test]
+    fn rel_axis_round_trips() {
+        let event = InputEvent::new(EventType::RELATIVE.0, RelAxisType::REL_X.0, -3);
+        assert_same(event, round_trip(event));
+    }
+
+    #[test]
+    fn unknown_event_type_falls_through_other() {
+        // An event type this crate does not model must round-trip via the raw-number Other variant.
+        let event = InputEvent::new(0x1f, 0x2a, 7);
+        let restored = round_trip(event);
+        assert_eq!(restored.event_type(), 0x1f);
+        assert_eq!(restored.code(), 0x2a);
+        assert_eq!(restored.value(), 7);
+    }
+}